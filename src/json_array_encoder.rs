@@ -0,0 +1,139 @@
+use futures::stream::{self, Stream, StreamExt};
+use serde::Serialize;
+use std::pin::Pin;
+
+/// Serializes each item of `stream` and emits the bytes of a well-formed JSON
+/// array incrementally: an opening `[` chunk, each element separated by `,`,
+/// and a closing `]` chunk. No chunk boundary corresponds to a full array, so
+/// this can be written straight to a body/socket without buffering it. If an
+/// item fails to serialize (e.g. a map with non-string keys), the stream
+/// yields that error and ends without emitting a closing `]`.
+pub fn to_json_array_stream<T, S>(
+    stream: S,
+) -> impl Stream<Item = Result<Vec<u8>, serde_json::Error>>
+where
+    S: Stream<Item = T>,
+    T: Serialize,
+{
+    encode(stream, None)
+}
+
+/// Like [`to_json_array_stream`], but inserts a newline and `indent` spaces
+/// before every element, mirroring the classic `Encoder`/`PrettyEncoder` split.
+pub fn to_json_array_stream_pretty<T, S>(
+    stream: S,
+    indent: usize,
+) -> impl Stream<Item = Result<Vec<u8>, serde_json::Error>>
+where
+    S: Stream<Item = T>,
+    T: Serialize,
+{
+    encode(stream, Some(indent))
+}
+
+enum Phase {
+    Open,
+    Elements { first: bool },
+    Done,
+}
+
+fn encode<T, S>(
+    stream: S,
+    indent: Option<usize>,
+) -> impl Stream<Item = Result<Vec<u8>, serde_json::Error>>
+where
+    S: Stream<Item = T>,
+    T: Serialize,
+{
+    let state = (Phase::Open, Box::pin(stream) as Pin<Box<S>>);
+    stream::unfold(state, move |(phase, mut inner)| async move {
+        match phase {
+            Phase::Open => Some((Ok(b"[".to_vec()), (Phase::Elements { first: true }, inner))),
+            Phase::Elements { first } => match inner.next().await {
+                Some(item) => {
+                    let mut chunk = Vec::new();
+                    if !first {
+                        chunk.push(b',');
+                    }
+                    if let Some(width) = indent {
+                        chunk.push(b'\n');
+                        chunk.extend(std::iter::repeat_n(b' ', width));
+                    }
+                    match serde_json::to_vec(&item) {
+                        Ok(bytes) => {
+                            chunk.extend(bytes);
+                            Some((Ok(chunk), (Phase::Elements { first: false }, inner)))
+                        }
+                        Err(err) => Some((Err(err), (Phase::Done, inner))),
+                    }
+                }
+                None => {
+                    let mut chunk = Vec::new();
+                    if indent.is_some() && !first {
+                        chunk.push(b'\n');
+                    }
+                    chunk.push(b']');
+                    Some((Ok(chunk), (Phase::Done, inner)))
+                }
+            },
+            Phase::Done => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn compact_array() {
+        let items = vec![1, 2, 3];
+        let stream = stream::iter(items);
+        let chunks: Vec<Vec<u8>> = to_json_array_stream(stream)
+            .map(|chunk| chunk.unwrap())
+            .collect()
+            .await;
+        let joined: Vec<u8> = chunks.concat();
+
+        assert_eq!(joined, b"[1,2,3]");
+    }
+
+    #[tokio::test]
+    async fn empty_array() {
+        let stream = stream::iter(Vec::<i32>::new());
+        let chunks: Vec<Vec<u8>> = to_json_array_stream(stream)
+            .map(|chunk| chunk.unwrap())
+            .collect()
+            .await;
+        let joined: Vec<u8> = chunks.concat();
+
+        assert_eq!(joined, b"[]");
+    }
+
+    #[tokio::test]
+    async fn pretty_array() {
+        let items = vec![1, 2];
+        let stream = stream::iter(items);
+        let chunks: Vec<Vec<u8>> = to_json_array_stream_pretty(stream, 2)
+            .map(|chunk| chunk.unwrap())
+            .collect()
+            .await;
+        let joined = String::from_utf8(chunks.concat()).unwrap();
+
+        assert_eq!(joined, "[\n  1,\n  2\n]");
+    }
+
+    #[tokio::test]
+    async fn serialize_error_ends_stream_without_closing_bracket() {
+        use std::collections::HashMap;
+
+        let mut item = HashMap::new();
+        item.insert(vec![1u8, 2, 3], 1);
+        let stream = stream::iter(vec![item]);
+        let chunks: Vec<_> = to_json_array_stream(stream).collect().await;
+
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].as_ref().is_ok());
+        assert!(chunks[1].as_ref().is_err());
+    }
+}
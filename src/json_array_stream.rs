@@ -0,0 +1,597 @@
+use crate::json_depth_analyzer::{ByteKind, JsonDepthAnalyzer, Mode, ParserError, PathSegment};
+use futures::{stream::Stream, task::Context};
+use std::pin::Pin;
+use std::task::Poll;
+use thiserror::Error;
+
+pub struct JsonArrayStream<S, B>
+where
+    S: Stream<Item = B>,
+    B: IntoIterator<Item = u8> + Sized,
+{
+    stream: S,
+    current: Option<B::IntoIter>,
+    analyzer: JsonDepthAnalyzer,
+    selector: Option<Vec<PathSegment>>,
+    started: bool,
+    finished: bool,
+    base_depth: usize,
+    buffer: Vec<u8>,
+    max_element_bytes: Option<usize>,
+    max_depth: Option<usize>,
+    /// In `Mode::Relaxed`, the offset in `buffer` of a structural `,` that
+    /// might turn out to be a trailing comma, held so it (and any whitespace
+    /// after it) can be dropped if the next non-comment byte closes the
+    /// enclosing `}`/`]` instead of starting another element.
+    pending_comma: Option<usize>,
+}
+
+pub fn stream_json_array<S, B>(stream: S) -> JsonArrayStream<S, B>
+where
+    S: Stream<Item = B>,
+    B: IntoIterator<Item = u8> + Sized,
+{
+    JsonArrayStreamBuilder::new(stream).build()
+}
+
+/// Like [`stream_json_array`], but yields the elements of the array nested at
+/// `path` (a sequence of object keys) instead of the outermost array. If the
+/// path is never reached, the resulting stream simply yields nothing.
+pub fn stream_json_array_at<S, B>(stream: S, path: &[&str]) -> JsonArrayStream<S, B>
+where
+    S: Stream<Item = B>,
+    B: IntoIterator<Item = u8> + Sized,
+{
+    JsonArrayStreamBuilder::new(stream).at(path).build()
+}
+
+/// Fluent entry point for configuring a [`JsonArrayStream`] beyond the
+/// defaults that [`stream_json_array`]/[`stream_json_array_at`] provide.
+pub struct JsonArrayStreamBuilder<S, B>
+where
+    S: Stream<Item = B>,
+    B: IntoIterator<Item = u8> + Sized,
+{
+    stream: S,
+    selector: Option<Vec<PathSegment>>,
+    mode: Mode,
+    strict_utf8: bool,
+    max_element_bytes: Option<usize>,
+    max_depth: Option<usize>,
+}
+
+impl<S, B> JsonArrayStreamBuilder<S, B>
+where
+    S: Stream<Item = B>,
+    B: IntoIterator<Item = u8> + Sized,
+{
+    pub fn new(stream: S) -> Self {
+        JsonArrayStreamBuilder {
+            stream,
+            selector: None,
+            mode: Mode::Strict,
+            strict_utf8: false,
+            max_element_bytes: None,
+            max_depth: None,
+        }
+    }
+
+    /// Yield the elements of the array nested at `path` instead of the
+    /// outermost array. See [`stream_json_array_at`].
+    pub fn at(mut self, path: &[&str]) -> Self {
+        self.selector = Some(
+            path.iter()
+                .map(|key| PathSegment::Key(key.to_string()))
+                .collect(),
+        );
+        self
+    }
+
+    /// Tolerate `//`, `#` and `/* */` comments and trailing commas, as
+    /// produced by JSONC/Hjson-style sources.
+    pub fn relaxed(mut self) -> Self {
+        self.mode = Mode::Relaxed;
+        self
+    }
+
+    /// Validate string contents: reject unpaired UTF-16 surrogates in
+    /// `\uXXXX` escapes and raw UTF-8 sequences with an invalid lead byte or
+    /// a missing or invalid continuation byte (overlong encodings and encoded
+    /// surrogates are not detected), instead of
+    /// letting them surface later as an opaque `serde_json` error. See
+    /// [`JsonDepthAnalyzer::with_strict_utf8`].
+    pub fn validate_utf8(mut self) -> Self {
+        self.strict_utf8 = true;
+        self
+    }
+
+    /// Fail the stream with [`JsonStreamError::ElementTooLarge`] rather than
+    /// growing an element's buffer past `limit` bytes. Guards against
+    /// unbounded memory use from a malformed or hostile stream, e.g. an
+    /// unterminated string or object whose closing delimiter never arrives.
+    /// The limit is enforced against the raw buffered bytes, so in
+    /// [`Self::relaxed`] mode a not-yet-resolved trailing comma still counts
+    /// even if it's later stripped; this keeps a held-open comma run from
+    /// buffering unboundedly while the stream waits to see what follows it.
+    pub fn max_element_bytes(mut self, limit: usize) -> Self {
+        self.max_element_bytes = Some(limit);
+        self
+    }
+
+    /// Fail the stream with [`JsonStreamError::DepthExceeded`] rather than
+    /// tracking structural nesting past `limit` levels deep.
+    pub fn max_depth(mut self, limit: usize) -> Self {
+        self.max_depth = Some(limit);
+        self
+    }
+
+    pub fn build(self) -> JsonArrayStream<S, B> {
+        let mut analyzer = JsonDepthAnalyzer::with_mode(self.mode);
+        if self.strict_utf8 {
+            analyzer = analyzer.with_strict_utf8();
+        }
+
+        JsonArrayStream {
+            stream: self.stream,
+            current: None,
+            analyzer,
+            selector: self.selector,
+            started: false,
+            finished: false,
+            base_depth: 1,
+            buffer: Vec::new(),
+            max_element_bytes: self.max_element_bytes,
+            max_depth: self.max_depth,
+            pending_comma: None,
+        }
+    }
+}
+
+impl<S, B> JsonArrayStream<S, B>
+where
+    S: Stream<Item = B>,
+    B: IntoIterator<Item = u8> + Sized,
+{
+    fn handle_byte(&mut self, c: u8) -> Result<Option<Vec<u8>>, JsonStreamError> {
+        if !self.started {
+            // A path selector matches right before the `[` it selects opens,
+            // i.e. while the live path still reflects the enclosing object.
+            // Discarded below if `c` turns out to be inside a comment.
+            let matches_selector = c == b'['
+                && !self.analyzer.in_string()
+                && self
+                    .selector
+                    .as_ref()
+                    .is_some_and(|selector| &self.analyzer.current_path() == selector);
+
+            if self.analyzer.process(c)? == ByteKind::Comment {
+                return Ok(None);
+            }
+            self.check_depth()?;
+
+            let array_opened = self.selector.is_none() || matches_selector;
+            if array_opened && self.analyzer.depth() >= 1 {
+                self.started = true;
+                self.base_depth = self.analyzer.depth();
+            }
+            return Ok(None);
+        }
+
+        if self.analyzer.depth() > self.base_depth {
+            let in_string = self.analyzer.in_string();
+            let kind = self.analyzer.process(c)?;
+            self.check_depth()?;
+
+            if kind == ByteKind::Comment {
+                return Ok(None);
+            }
+
+            // While a trailing-comma decision is pending, whitespace and
+            // further structural commas (e.g. `,,`) just extend the run
+            // being held; `start` stays pinned to the first comma in it so
+            // the whole run is truncated together if it turns out trailing.
+            if let Some(start) = self.pending_comma {
+                if c.is_ascii_whitespace() || (self.analyzer.is_relaxed() && !in_string && c == b',')
+                {
+                    self.push_to_buffer(c)?;
+                    return Ok(None);
+                }
+                self.pending_comma = None;
+                if matches!(c, b'}' | b']') {
+                    self.buffer.truncate(start);
+                }
+                self.push_to_buffer(c)?;
+                return Ok(None);
+            }
+
+            // A structural `,` (not one inside a string value) might be a
+            // trailing comma; defer buffering the decision until the next
+            // non-comment byte shows whether a `}`/`]` follows.
+            if self.analyzer.is_relaxed() && !in_string && c == b',' {
+                self.pending_comma = Some(self.buffer.len());
+                self.push_to_buffer(c)?;
+                return Ok(None);
+            }
+
+            self.push_to_buffer(c)?;
+            return Ok(None);
+        }
+
+        // depth() == base_depth: directly inside the target array, between
+        // elements. A comment here (only possible in `Mode::Relaxed`) is
+        // dropped without affecting the surrounding `,`/`]` bookkeeping.
+        let kind = self.analyzer.process(c)?;
+        if kind == ByteKind::Comment {
+            return Ok(None);
+        }
+        self.check_depth()?;
+
+        match c {
+            b',' => Ok(if self.buffer.is_empty() {
+                None
+            } else {
+                Some(std::mem::take(&mut self.buffer))
+            }),
+            b']' => {
+                self.finished = true;
+                Ok(if self.buffer.is_empty() {
+                    None
+                } else {
+                    Some(std::mem::take(&mut self.buffer))
+                })
+            }
+            c if c.is_ascii_whitespace() && self.buffer.is_empty() => Ok(None),
+            c => {
+                self.push_to_buffer(c)?;
+                Ok(None)
+            }
+        }
+    }
+
+    fn check_depth(&self) -> Result<(), JsonStreamError> {
+        match self.max_depth {
+            Some(limit) if self.analyzer.depth() > limit => {
+                Err(JsonStreamError::DepthExceeded { limit })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn push_to_buffer(&mut self, c: u8) -> Result<(), JsonStreamError> {
+        if let Some(limit) = self.max_element_bytes {
+            if self.buffer.len() >= limit {
+                return Err(JsonStreamError::ElementTooLarge { limit });
+            }
+        }
+        self.buffer.push(c);
+        Ok(())
+    }
+}
+
+impl<S, B> Stream for JsonArrayStream<S, B>
+where
+    S: Stream<Item = B>,
+    B: IntoIterator<Item = u8> + Sized,
+{
+    type Item = Result<Vec<u8>, JsonStreamError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = unsafe { self.get_unchecked_mut() };
+        loop {
+            if this.finished {
+                return Poll::Ready(None);
+            }
+
+            if this.current.is_some() {
+                let next = this.current.as_mut().and_then(Iterator::next);
+                match next {
+                    Some(c) => match this.handle_byte(c) {
+                        Ok(Some(element)) => return Poll::Ready(Some(Ok(element))),
+                        Ok(None) => continue,
+                        Err(err) => {
+                            this.finished = true;
+                            return Poll::Ready(Some(Err(err)));
+                        }
+                    },
+                    None => {
+                        this.current = None;
+                        continue;
+                    }
+                }
+            }
+
+            match unsafe { Pin::new_unchecked(&mut this.stream) }.poll_next(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(None) => {
+                    this.finished = true;
+                    return Poll::Ready(None);
+                }
+                Poll::Ready(Some(chunk)) => {
+                    this.current = Some(chunk.into_iter());
+                }
+            }
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum JsonStreamError {
+    #[error(transparent)]
+    Parser(#[from] ParserError),
+    #[error(transparent)]
+    Deserialize(#[from] serde_json::Error),
+    #[error("element exceeded max_element_bytes ({limit})")]
+    ElementTooLarge { limit: usize },
+    #[error("nesting depth exceeded max_depth ({limit})")]
+    DepthExceeded { limit: usize },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::stream::TryStreamExt;
+
+    #[tokio::test]
+    async fn yields_raw_element_buffers() {
+        let json = "[-12,11.1,0]";
+        let stream = futures::stream::once(async { json.bytes() });
+        let elements: Vec<_> = stream_json_array(stream).try_collect().await.unwrap();
+
+        assert_eq!(
+            elements,
+            vec![b"-12".to_vec(), b"11.1".to_vec(), b"0".to_vec()]
+        );
+    }
+
+    #[tokio::test]
+    async fn empty_array() {
+        let json = "[]";
+        let stream = futures::stream::once(async { json.bytes() });
+        let elements: Vec<Vec<u8>> = stream_json_array(stream).try_collect().await.unwrap();
+
+        assert!(elements.is_empty());
+    }
+
+    #[tokio::test]
+    async fn nested_object_elements() {
+        let json = r#"[{"a": 1}, {"b": 2}]"#;
+        let stream = futures::stream::once(async { json.bytes() });
+        let elements: Vec<_> = stream_json_array(stream).try_collect().await.unwrap();
+
+        assert_eq!(
+            elements,
+            vec![br#"{"a": 1}"#.to_vec(), br#"{"b": 2}"#.to_vec()]
+        );
+    }
+
+    #[tokio::test]
+    async fn array_at_nested_path() {
+        let json = r#"{"data":{"items":[1,2,3],"total":3}}"#;
+        let stream = futures::stream::once(async { json.bytes() });
+        let elements: Vec<_> = stream_json_array_at(stream, &["data", "items"])
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            elements,
+            vec![b"1".to_vec(), b"2".to_vec(), b"3".to_vec()]
+        );
+    }
+
+    #[tokio::test]
+    async fn array_at_path_that_never_matches() {
+        let json = r#"{"data":{"items":[1,2,3]}}"#;
+        let stream = futures::stream::once(async { json.bytes() });
+        let elements: Vec<Vec<u8>> = stream_json_array_at(stream, &["nope"])
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert!(elements.is_empty());
+    }
+
+    #[tokio::test]
+    async fn relaxed_mode_strips_line_and_block_comments() {
+        let json = "[\n  // a leading comment\n  1, /* inline */2# trailing\n]";
+        let stream = futures::stream::once(async { json.bytes() });
+        let elements: Vec<_> = JsonArrayStreamBuilder::new(stream)
+            .relaxed()
+            .build()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(elements, vec![b"1".to_vec(), b"2".to_vec()]);
+    }
+
+    #[tokio::test]
+    async fn relaxed_mode_tolerates_trailing_comma() {
+        let json = "[1, 2, ]";
+        let stream = futures::stream::once(async { json.bytes() });
+        let elements: Vec<_> = JsonArrayStreamBuilder::new(stream)
+            .relaxed()
+            .build()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(elements, vec![b"1".to_vec(), b"2".to_vec()]);
+    }
+
+    #[tokio::test]
+    async fn relaxed_mode_strips_trailing_comma_inside_nested_element() {
+        let json = r#"[{"a":1,},2]"#;
+        let stream = futures::stream::once(async { json.bytes() });
+        let elements: Vec<_> = JsonArrayStreamBuilder::new(stream)
+            .relaxed()
+            .build()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(elements, vec![br#"{"a":1}"#.to_vec(), b"2".to_vec()]);
+        serde_json::from_slice::<serde_json::Value>(&elements[0])
+            .expect("stripped element should be strict-JSON parseable");
+    }
+
+    #[tokio::test]
+    async fn relaxed_mode_strips_trailing_comma_in_nested_array() {
+        let json = "[[1,2,],3]";
+        let stream = futures::stream::once(async { json.bytes() });
+        let elements: Vec<_> = JsonArrayStreamBuilder::new(stream)
+            .relaxed()
+            .build()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(elements, vec![b"[1,2]".to_vec(), b"3".to_vec()]);
+    }
+
+    #[tokio::test]
+    async fn relaxed_mode_strips_run_of_consecutive_trailing_commas() {
+        let json = r#"[{"a":1,,},2]"#;
+        let stream = futures::stream::once(async { json.bytes() });
+        let elements: Vec<_> = JsonArrayStreamBuilder::new(stream)
+            .relaxed()
+            .build()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(elements, vec![br#"{"a":1}"#.to_vec(), b"2".to_vec()]);
+        serde_json::from_slice::<serde_json::Value>(&elements[0])
+            .expect("stripped element should be strict-JSON parseable");
+    }
+
+    #[tokio::test]
+    async fn relaxed_mode_does_not_strip_comma_inside_string() {
+        let json = r#"[{"a":"x,"}]"#;
+        let stream = futures::stream::once(async { json.bytes() });
+        let elements: Vec<_> = JsonArrayStreamBuilder::new(stream)
+            .relaxed()
+            .build()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(elements, vec![br#"{"a":"x,"}"#.to_vec()]);
+    }
+
+    #[tokio::test]
+    async fn relaxed_mode_ignores_comment_containing_brackets() {
+        let json = "[1, /* [not, real] */ 2]";
+        let stream = futures::stream::once(async { json.bytes() });
+        let elements: Vec<_> = JsonArrayStreamBuilder::new(stream)
+            .relaxed()
+            .build()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(elements, vec![b"1".to_vec(), b"2".to_vec()]);
+    }
+
+    #[tokio::test]
+    async fn validate_utf8_rejects_lone_surrogate() {
+        let json = "[\"\\ud83d\"]";
+        let stream = futures::stream::once(async { json.bytes() });
+        let result: Result<Vec<_>, _> = JsonArrayStreamBuilder::new(stream)
+            .validate_utf8()
+            .build()
+            .try_collect()
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn without_validate_utf8_lone_surrogate_is_passed_through() {
+        let json = "[\"\\ud83d\"]";
+        let stream = futures::stream::once(async { json.bytes() });
+        let elements: Vec<_> = stream_json_array(stream).try_collect().await.unwrap();
+
+        assert_eq!(elements, vec![br#""\ud83d""#.to_vec()]);
+    }
+
+    #[tokio::test]
+    async fn max_element_bytes_rejects_oversized_element() {
+        let json = r#"["this element is too long"]"#;
+        let stream = futures::stream::once(async { json.bytes() });
+        let result: Result<Vec<_>, _> = JsonArrayStreamBuilder::new(stream)
+            .max_element_bytes(8)
+            .build()
+            .try_collect()
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(JsonStreamError::ElementTooLarge { limit: 8 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn max_element_bytes_allows_elements_within_limit() {
+        let json = "[1,22,333]";
+        let stream = futures::stream::once(async { json.bytes() });
+        let elements: Vec<_> = JsonArrayStreamBuilder::new(stream)
+            .max_element_bytes(3)
+            .build()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            elements,
+            vec![b"1".to_vec(), b"22".to_vec(), b"333".to_vec()]
+        );
+    }
+
+    #[tokio::test]
+    async fn max_element_bytes_catches_unterminated_string() {
+        // An unterminated string/object would otherwise buffer forever.
+        let json = r#"["unterminated"#;
+        let stream = futures::stream::once(async { json.bytes() });
+        let result: Result<Vec<_>, _> = JsonArrayStreamBuilder::new(stream)
+            .max_element_bytes(4)
+            .build()
+            .try_collect()
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(JsonStreamError::ElementTooLarge { limit: 4 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn max_depth_rejects_deeply_nested_element() {
+        let json = "[[[1]]]";
+        let stream = futures::stream::once(async { json.bytes() });
+        let result: Result<Vec<_>, _> = JsonArrayStreamBuilder::new(stream)
+            .max_depth(2)
+            .build()
+            .try_collect()
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(JsonStreamError::DepthExceeded { limit: 2 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn max_depth_allows_nesting_within_limit() {
+        let json = r#"[{"a": 1}]"#;
+        let stream = futures::stream::once(async { json.bytes() });
+        let elements: Vec<_> = JsonArrayStreamBuilder::new(stream)
+            .max_depth(3)
+            .build()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(elements, vec![br#"{"a": 1}"#.to_vec()]);
+    }
+}
@@ -2,131 +2,480 @@ use thiserror::Error;
 
 #[derive(Debug, Clone)]
 pub struct JsonDepthAnalyzer {
-    state: Vec<ParserState>,
+    frames: Vec<Frame>,
+    string: Option<StringScan>,
+    comment: Option<CommentScan>,
+    mode: Mode,
+    strict_utf8: bool,
 }
 
 impl JsonDepthAnalyzer {
     pub fn new() -> JsonDepthAnalyzer {
-        JsonDepthAnalyzer { state: vec![] }
+        Self::with_mode(Mode::Strict)
     }
 
+    pub fn with_mode(mode: Mode) -> JsonDepthAnalyzer {
+        JsonDepthAnalyzer {
+            frames: vec![],
+            string: None,
+            comment: None,
+            mode,
+            strict_utf8: false,
+        }
+    }
+
+    /// Opt in to validating string contents: `\uXXXX` escapes that encode a
+    /// UTF-16 high surrogate must be immediately followed by a matching low
+    /// surrogate escape, and raw (unescaped) multi-byte UTF-8 sequences must
+    /// have well-formed continuation bytes. Off by default, since most
+    /// streams come from conformant encoders and the checks cost a branch
+    /// per string byte.
+    pub fn with_strict_utf8(mut self) -> Self {
+        self.strict_utf8 = true;
+        self
+    }
+
+    /// Structural depth: one per open `Object`/`Array` frame, plus one more
+    /// while a string literal is still open. Comments never affect depth.
     pub fn depth(&self) -> usize {
-        self.state.len()
+        self.frames.len() + if self.string.is_some() { 1 } else { 0 }
     }
 
-    pub fn process(&mut self, c: u8) -> Result<(), ParserError> {
-        match (self.state.last(), c) {
-            (Some(ParserState::String), b'"') => {
-                self.state.pop();
-                Ok(())
+    pub fn is_relaxed(&self) -> bool {
+        self.mode == Mode::Relaxed
+    }
+
+    /// Whether the byte about to be processed would fall inside an
+    /// already-open string literal, as opposed to being JSON structure.
+    pub fn in_string(&self) -> bool {
+        self.string.is_some()
+    }
+
+    /// The key/index of the `Object`/`Array` frame currently being parsed, at
+    /// each nesting level. An `Object` frame contributes nothing until its
+    /// key has been fully parsed.
+    pub fn current_path(&self) -> Vec<PathSegment> {
+        self.frames
+            .iter()
+            .filter_map(|frame| match frame {
+                Frame::Object(o) => o.current_key.clone().map(PathSegment::Key),
+                Frame::Array(a) => Some(PathSegment::Index(a.current_index)),
+            })
+            .collect()
+    }
+
+    pub fn process(&mut self, c: u8) -> Result<ByteKind, ParserError> {
+        if self.comment.is_some() {
+            self.process_comment_byte(c)?;
+            return Ok(ByteKind::Comment);
+        }
+
+        if self.string.is_some() {
+            self.process_string_byte(c)?;
+            return Ok(ByteKind::Structural);
+        }
+
+        if self.mode == Mode::Relaxed {
+            match c {
+                b'#' => {
+                    self.comment = Some(CommentScan {
+                        sub: CommentSub::Line,
+                    });
+                    return Ok(ByteKind::Comment);
+                }
+                b'/' => {
+                    self.comment = Some(CommentScan {
+                        sub: CommentSub::MaybeBlockOrLine,
+                    });
+                    return Ok(ByteKind::Comment);
+                }
+                _ => {}
             }
+        }
+
+        match (self.frames.last(), c) {
             (_, b'"') => {
-                self.state.push(ParserState::String);
+                let capturing = matches!(
+                    self.frames.last(),
+                    Some(Frame::Object(o)) if o.expect_key && o.current_key.is_none()
+                );
+                self.string = Some(StringScan {
+                    sub: StringSub::Normal,
+                    buffer: capturing.then(Vec::new),
+                    hex_value: 0,
+                    pending_high_surrogate: None,
+                });
+                Ok(ByteKind::Structural)
+            }
+
+            (_, b'{') => {
+                self.frames.push(Frame::Object(ObjectFrame {
+                    current_key: None,
+                    expect_key: true,
+                }));
+                Ok(ByteKind::Structural)
+            }
+            (Some(Frame::Object(_)), b'}') => {
+                self.frames.pop();
+                Ok(ByteKind::Structural)
+            }
+            (got, b'}') => Err(ParserError::WrongState {
+                got: got.map(Frame::kind),
+                expected: FrameKind::Object,
+            }),
+
+            (_, b'[') => {
+                self.frames.push(Frame::Array(ArrayFrame::default()));
+                Ok(ByteKind::Structural)
+            }
+            (Some(Frame::Array(_)), b']') => {
+                self.frames.pop();
+                Ok(ByteKind::Structural)
+            }
+            (got, b']') => Err(ParserError::WrongState {
+                got: got.map(Frame::kind),
+                expected: FrameKind::Array,
+            }),
+
+            (Some(Frame::Object(_)), b':') => {
+                if let Some(Frame::Object(o)) = self.frames.last_mut() {
+                    o.expect_key = false;
+                }
+                Ok(ByteKind::Structural)
+            }
+            (Some(Frame::Object(_)), b',') => {
+                if let Some(Frame::Object(o)) = self.frames.last_mut() {
+                    o.expect_key = true;
+                    o.current_key = None;
+                }
+                Ok(ByteKind::Structural)
+            }
+            (Some(Frame::Array(_)), b',') => {
+                if let Some(Frame::Array(a)) = self.frames.last_mut() {
+                    a.current_index += 1;
+                }
+                Ok(ByteKind::Structural)
+            }
+
+            _ => Ok(ByteKind::Structural),
+        }
+    }
+
+    fn process_comment_byte(&mut self, c: u8) -> Result<(), ParserError> {
+        let sub = self.comment.as_ref().unwrap().sub;
+        match (sub, c) {
+            (CommentSub::MaybeBlockOrLine, b'/') => {
+                self.comment.as_mut().unwrap().sub = CommentSub::Line;
                 Ok(())
             }
-            (Some(ParserState::String), b'\\') => {
-                *self.state.last_mut().unwrap() = ParserState::StringEscape;
+            (CommentSub::MaybeBlockOrLine, b'*') => {
+                self.comment.as_mut().unwrap().sub = CommentSub::Block;
                 Ok(())
             }
-            (Some(ParserState::StringEscape), b'u') => {
-                *self.state.last_mut().unwrap() = ParserState::StringHex4;
+            (CommentSub::MaybeBlockOrLine, c) => Err(ParserError::InvalidComment { got: c }),
+            (CommentSub::Line, b'\n') => {
+                self.comment = None;
                 Ok(())
             }
-            (Some(ParserState::StringHex4), c) => {
-                *self.state.last_mut().unwrap() = ParserState::StringHex3;
-                if c.is_ascii_hexdigit() {
-                    Ok(())
-                } else {
-                    Err(ParserError::WrongHexCharacter { got: c })
-                }
+            (CommentSub::Line, _) => Ok(()),
+            (CommentSub::Block, b'*') => {
+                self.comment.as_mut().unwrap().sub = CommentSub::BlockStar;
+                Ok(())
             }
-            (Some(ParserState::StringHex3), c) => {
-                *self.state.last_mut().unwrap() = ParserState::StringHex2;
-                if c.is_ascii_hexdigit() {
-                    Ok(())
-                } else {
-                    Err(ParserError::WrongHexCharacter { got: c })
-                }
+            (CommentSub::Block, _) => Ok(()),
+            (CommentSub::BlockStar, b'/') => {
+                self.comment = None;
+                Ok(())
             }
-            (Some(ParserState::StringHex2), c) => {
-                *self.state.last_mut().unwrap() = ParserState::StringHex1;
-                if c.is_ascii_hexdigit() {
-                    Ok(())
-                } else {
-                    Err(ParserError::WrongHexCharacter { got: c })
+            (CommentSub::BlockStar, b'*') => Ok(()),
+            (CommentSub::BlockStar, _) => {
+                self.comment.as_mut().unwrap().sub = CommentSub::Block;
+                Ok(())
+            }
+        }
+    }
+
+    fn process_string_byte(&mut self, c: u8) -> Result<(), ParserError> {
+        let scan = self.string.as_ref().unwrap();
+        let sub = scan.sub;
+
+        if self.strict_utf8
+            && scan.pending_high_surrogate.is_some()
+            && !matches!((sub, c), (StringSub::Normal, b'\\') | (StringSub::Escape, b'u'))
+            && !matches!(sub, StringSub::Hex4 | StringSub::Hex3 | StringSub::Hex2 | StringSub::Hex1)
+        {
+            return Err(ParserError::LoneSurrogate);
+        }
+
+        match (sub, c) {
+            (StringSub::Normal, b'"') => {
+                let scan = self.string.take().unwrap();
+                if let Some(buffer) = scan.buffer {
+                    self.capture_key(buffer);
                 }
+                Ok(())
             }
-            (Some(ParserState::StringHex1), c) => {
-                *self.state.last_mut().unwrap() = ParserState::String;
-                if c.is_ascii_hexdigit() {
-                    Ok(())
-                } else {
-                    Err(ParserError::WrongHexCharacter { got: c })
+            (StringSub::Normal, b'\\') => {
+                self.push_string_byte(c, StringSub::Escape);
+                Ok(())
+            }
+            (StringSub::Escape, b'u') => {
+                self.push_string_byte(c, StringSub::Hex4);
+                self.string.as_mut().unwrap().hex_value = 0;
+                Ok(())
+            }
+            (StringSub::Hex4, c) => {
+                self.accumulate_hex_digit(c, StringSub::Hex3)
+            }
+            (StringSub::Hex3, c) => {
+                self.accumulate_hex_digit(c, StringSub::Hex2)
+            }
+            (StringSub::Hex2, c) => {
+                self.accumulate_hex_digit(c, StringSub::Hex1)
+            }
+            (StringSub::Hex1, c) => {
+                self.accumulate_hex_digit(c, StringSub::Normal)?;
+                if self.strict_utf8 {
+                    self.finish_unicode_escape()?;
                 }
+                Ok(())
             }
-            (Some(ParserState::StringEscape), c) => {
-                *self.state.last_mut().unwrap() = ParserState::String;
+            (StringSub::Escape, c) => {
+                self.push_string_byte(c, StringSub::Normal);
                 if "\"\\/bfnrt".bytes().any(|e| c == e) {
                     Ok(())
                 } else {
                     Err(ParserError::WrongEscapeCharacter { got: c })
                 }
             }
-            (Some(ParserState::String), _) => Ok(()),
-
-            (_, b'{') => {
-                self.state.push(ParserState::Object);
+            (StringSub::Utf8Continuation(remaining), c) => {
+                if !(0x80..=0xBF).contains(&c) {
+                    return Err(ParserError::InvalidUtf8 { got: c });
+                }
+                let next = if remaining > 1 {
+                    StringSub::Utf8Continuation(remaining - 1)
+                } else {
+                    StringSub::Normal
+                };
+                self.push_string_byte(c, next);
                 Ok(())
             }
-            (Some(ParserState::Object), b'}') => {
-                self.state.pop();
+            (StringSub::Normal, c) if self.strict_utf8 => {
+                let next = match c {
+                    0x00..=0x7F => StringSub::Normal,
+                    0xC2..=0xDF => StringSub::Utf8Continuation(1),
+                    0xE0..=0xEF => StringSub::Utf8Continuation(2),
+                    0xF0..=0xF4 => StringSub::Utf8Continuation(3),
+                    _ => return Err(ParserError::InvalidUtf8 { got: c }),
+                };
+                self.push_string_byte(c, next);
                 Ok(())
             }
-            (got, b'}') => Err(ParserError::WrongState {
-                got: got.cloned(),
-                expected: ParserState::Object,
-            }),
-            (_, b'[') => {
-                self.state.push(ParserState::Array);
+            (StringSub::Normal, c) => {
+                self.push_string_byte(c, StringSub::Normal);
                 Ok(())
             }
-            (Some(ParserState::Array), b']') => {
-                self.state.pop();
-                Ok(())
+        }
+    }
+
+    /// Folds one more hex digit of a `\uXXXX` escape into the scan's
+    /// accumulated value, in addition to the existing digit validation.
+    fn accumulate_hex_digit(&mut self, c: u8, next: StringSub) -> Result<(), ParserError> {
+        Self::require_hex_digit(c)?;
+        let digit = (c as char).to_digit(16).unwrap() as u16;
+        let scan = self.string.as_mut().unwrap();
+        scan.hex_value = (scan.hex_value << 4) | digit;
+        self.push_string_byte(c, next);
+        Ok(())
+    }
+
+    /// Called once a `\uXXXX` escape's four digits are all consumed, to
+    /// enforce that high and low UTF-16 surrogates only ever appear paired.
+    fn finish_unicode_escape(&mut self) -> Result<(), ParserError> {
+        let scan = self.string.as_mut().unwrap();
+        let value = scan.hex_value;
+
+        if scan.pending_high_surrogate.take().is_some() {
+            if !(0xDC00..=0xDFFF).contains(&value) {
+                return Err(ParserError::LoneSurrogate);
             }
-            (got, b']') => Err(ParserError::WrongState {
-                got: got.cloned(),
-                expected: ParserState::Array,
-            }),
-            _ => Ok(()),
+            Ok(())
+        } else if (0xD800..=0xDBFF).contains(&value) {
+            scan.pending_high_surrogate = Some(value);
+            Ok(())
+        } else if (0xDC00..=0xDFFF).contains(&value) {
+            Err(ParserError::LoneSurrogate)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn push_string_byte(&mut self, c: u8, next: StringSub) {
+        let scan = self.string.as_mut().unwrap();
+        scan.push(c);
+        scan.sub = next;
+    }
+
+    fn require_hex_digit(c: u8) -> Result<(), ParserError> {
+        if c.is_ascii_hexdigit() {
+            Ok(())
+        } else {
+            Err(ParserError::WrongHexCharacter { got: c })
+        }
+    }
+
+    fn capture_key(&mut self, buffer: Vec<u8>) {
+        let Some(Frame::Object(o)) = self.frames.last_mut() else {
+            return;
+        };
+        if !(o.expect_key && o.current_key.is_none()) {
+            return;
+        }
+
+        let mut quoted = Vec::with_capacity(buffer.len() + 2);
+        quoted.push(b'"');
+        quoted.extend(buffer);
+        quoted.push(b'"');
+        if let Ok(key) = serde_json::from_slice::<String>(&quoted) {
+            o.current_key = Some(key);
         }
     }
 }
 
-#[derive(Error, Debug, Clone)]
+impl Default for JsonDepthAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Frame {
+    Object(ObjectFrame),
+    Array(ArrayFrame),
+}
+
+impl Frame {
+    fn kind(&self) -> FrameKind {
+        match self {
+            Frame::Object(_) => FrameKind::Object,
+            Frame::Array(_) => FrameKind::Array,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ObjectFrame {
+    /// The key whose value is currently being parsed, once its closing quote
+    /// has been seen. `None` while scanning the key itself.
+    current_key: Option<String>,
+    /// Whether the next string at this depth is a key rather than a value.
+    expect_key: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+struct ArrayFrame {
+    /// The index of the element currently being parsed.
+    current_index: usize,
+}
+
+#[derive(Debug, Clone)]
+struct StringScan {
+    sub: StringSub,
+    /// Raw (still-escaped) bytes of the string, only accumulated when the
+    /// string might be used as an object key.
+    buffer: Option<Vec<u8>>,
+    /// The `\uXXXX` value accumulated so far, one hex digit at a time.
+    hex_value: u16,
+    /// Set once a `\uXXXX` escape turns out to encode a UTF-16 high
+    /// surrogate, until the matching low surrogate escape is confirmed.
+    /// Only ever populated when `strict_utf8` is enabled.
+    pending_high_surrogate: Option<u16>,
+}
+
+impl StringScan {
+    fn push(&mut self, c: u8) {
+        if let Some(buffer) = self.buffer.as_mut() {
+            buffer.push(c);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StringSub {
+    Normal,
+    Escape,
+    Hex4,
+    Hex3,
+    Hex2,
+    Hex1,
+    /// Inside a raw (unescaped) multi-byte UTF-8 sequence, this many
+    /// continuation bytes still remain. Only entered when `strict_utf8` is
+    /// enabled.
+    Utf8Continuation(u8),
+}
+
+#[derive(Debug, Clone)]
+struct CommentScan {
+    sub: CommentSub,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CommentSub {
+    /// Just saw a `/`; still waiting to see whether it's `//` or `/*`.
+    MaybeBlockOrLine,
+    Line,
+    Block,
+    /// Inside a block comment, just saw a `*`; waiting to see if a `/` follows.
+    BlockStar,
+}
+
+/// Whether [`JsonDepthAnalyzer::process`] consumed a byte as comment noise
+/// (only possible in [`Mode::Relaxed`]) or as ordinary JSON structure/content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteKind {
+    Structural,
+    Comment,
+}
+
+/// Parsing mode: [`Mode::Relaxed`] additionally tolerates `//`, `/* */` and
+/// `#` comments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Strict,
+    Relaxed,
+}
+
+/// A single segment of the path to the value currently being parsed, as
+/// exposed by [`JsonDepthAnalyzer::current_path`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ParserError {
     #[error("expected state Some({expected:?}), got ({got:?})")]
     WrongState {
-        got: Option<ParserState>,
-        expected: ParserState,
+        got: Option<FrameKind>,
+        expected: FrameKind,
     },
     #[error("expected hex character, got '{got}'")]
     WrongHexCharacter { got: u8 },
     #[error("expected escape sequence, got \"{got}\"")]
     WrongEscapeCharacter { got: u8 },
+    #[error("expected '/' or '*' to start a comment, got \"{got}\"")]
+    InvalidComment { got: u8 },
+    #[error("UTF-16 surrogate in \\u escape is not paired with a matching surrogate")]
+    LoneSurrogate,
+    #[error("invalid UTF-8 byte '{got:#04x}' in string")]
+    InvalidUtf8 { got: u8 },
 }
 
-#[derive(Debug, Copy, Clone)]
-#[repr(u8)]
-pub enum ParserState {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameKind {
     Object,
     Array,
-    String,
-    StringEscape,
-    StringHex4,
-    StringHex3,
-    StringHex2,
-    StringHex1,
 }
 
 #[cfg(test)]
@@ -161,7 +510,7 @@ mod tests {
     fn wrong_nesting() {
         let mut parser = JsonDepthAnalyzer::new();
         let json = "[{]}";
-        assert_eq!(json.bytes().all(|c| parser.process(c).is_ok()), false);
+        assert!(!json.bytes().all(|c| parser.process(c).is_ok()));
     }
 
     #[test]
@@ -213,7 +562,7 @@ mod tests {
     fn invalid_escape() {
         let mut parser = JsonDepthAnalyzer::new();
         let json = "\"\\x";
-        assert_eq!(json.bytes().all(|c| parser.process(c).is_ok()), false);
+        assert!(!json.bytes().all(|c| parser.process(c).is_ok()));
         assert_eq!(parser.depth(), 1);
     }
 
@@ -221,7 +570,133 @@ mod tests {
     fn invalid_unicode() {
         let mut parser = JsonDepthAnalyzer::new();
         let json = "\"\\u123x";
-        assert_eq!(json.bytes().all(|c| parser.process(c).is_ok()), false);
+        assert!(!json.bytes().all(|c| parser.process(c).is_ok()));
         assert_eq!(parser.depth(), 1);
     }
+
+    #[test]
+    fn tracks_object_key_path() {
+        let mut parser = JsonDepthAnalyzer::new();
+        let json = r#"{"data":{"items":"#;
+        assert!(json.bytes().all(|c| parser.process(c).is_ok()));
+        assert_eq!(
+            parser.current_path(),
+            vec![
+                PathSegment::Key("data".to_string()),
+                PathSegment::Key("items".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn tracks_array_index_path() {
+        let mut parser = JsonDepthAnalyzer::new();
+        let json = r#"["a","b","#;
+        assert!(json.bytes().all(|c| parser.process(c).is_ok()));
+        assert_eq!(parser.current_path(), vec![PathSegment::Index(2)]);
+    }
+
+    #[test]
+    fn escaped_key_is_unescaped() {
+        let mut parser = JsonDepthAnalyzer::new();
+        let json = r#"{"a\"b":"#;
+        assert!(json.bytes().all(|c| parser.process(c).is_ok()));
+        assert_eq!(
+            parser.current_path(),
+            vec![PathSegment::Key("a\"b".to_string())]
+        );
+    }
+
+    #[test]
+    fn relaxed_mode_skips_line_and_block_comments() {
+        let mut parser = JsonDepthAnalyzer::with_mode(Mode::Relaxed);
+        let json = "[1, // one\n2, /* two */ 3]";
+        let kinds: Vec<_> = json.bytes().map(|c| parser.process(c).unwrap()).collect();
+
+        assert_eq!(parser.depth(), 0);
+        assert_eq!(
+            kinds.iter().filter(|k| **k == ByteKind::Comment).count(),
+            "// one\n".len() + "/* two */".len()
+        );
+    }
+
+    #[test]
+    fn relaxed_mode_comment_does_not_affect_depth() {
+        let mut parser = JsonDepthAnalyzer::with_mode(Mode::Relaxed);
+        let json = "[/* [{\"x\":[ */]";
+        assert!(json.bytes().all(|c| parser.process(c).is_ok()));
+        assert_eq!(parser.depth(), 0);
+    }
+
+    #[test]
+    fn relaxed_mode_invalid_comment_start() {
+        let mut parser = JsonDepthAnalyzer::with_mode(Mode::Relaxed);
+        let json = "[/x";
+        assert!(!json.bytes().all(|c| parser.process(c).is_ok()));
+    }
+
+    #[test]
+    fn strict_utf8_allows_surrogate_pair() {
+        let mut parser = JsonDepthAnalyzer::new().with_strict_utf8();
+        let json = concat!("\"\\", "ud83d\\", "ude00\"");
+        assert!(json.bytes().all(|c| parser.process(c).is_ok()));
+        assert_eq!(parser.depth(), 0);
+    }
+
+    #[test]
+    fn strict_utf8_rejects_lone_high_surrogate() {
+        let mut parser = JsonDepthAnalyzer::new().with_strict_utf8();
+        let json = r#""\ud83d""#;
+        assert!(!json.bytes().all(|c| parser.process(c).is_ok()));
+    }
+
+    #[test]
+    fn strict_utf8_rejects_lone_low_surrogate() {
+        let mut parser = JsonDepthAnalyzer::new().with_strict_utf8();
+        let json = r#""\ude00""#;
+        assert!(!json.bytes().all(|c| parser.process(c).is_ok()));
+    }
+
+    #[test]
+    fn strict_utf8_rejects_high_surrogate_without_following_escape() {
+        let mut parser = JsonDepthAnalyzer::new().with_strict_utf8();
+        let json = r#""\ud83dx""#;
+        assert!(!json.bytes().all(|c| parser.process(c).is_ok()));
+    }
+
+    #[test]
+    fn non_strict_mode_allows_lone_surrogate() {
+        let mut parser = JsonDepthAnalyzer::new();
+        let json = r#""\ud83d""#;
+        assert!(json.bytes().all(|c| parser.process(c).is_ok()));
+    }
+
+    #[test]
+    fn strict_utf8_allows_valid_multibyte_sequence() {
+        let mut parser = JsonDepthAnalyzer::new().with_strict_utf8();
+        let json = "\"caf\u{e9} \u{1f600}\"";
+        assert!(json.bytes().all(|c| parser.process(c).is_ok()));
+        assert_eq!(parser.depth(), 0);
+    }
+
+    #[test]
+    fn strict_utf8_rejects_stray_continuation_byte() {
+        let mut parser = JsonDepthAnalyzer::new().with_strict_utf8();
+        let json: &[u8] = b"\"\x80\"";
+        assert!(!json.iter().all(|&c| parser.process(c).is_ok()));
+    }
+
+    #[test]
+    fn strict_utf8_rejects_truncated_multibyte_sequence() {
+        let mut parser = JsonDepthAnalyzer::new().with_strict_utf8();
+        let json: &[u8] = b"\"\xe2\x82\"";
+        assert!(!json.iter().all(|&c| parser.process(c).is_ok()));
+    }
+
+    #[test]
+    fn non_strict_mode_allows_stray_continuation_byte() {
+        let mut parser = JsonDepthAnalyzer::new();
+        let json: &[u8] = b"\"\x80\"";
+        assert!(json.iter().all(|&c| parser.process(c).is_ok()));
+    }
 }
@@ -3,11 +3,15 @@ use std::marker::PhantomData;
 use std::pin::Pin;
 use std::task::Poll;
 
+mod json_array_encoder;
 mod json_array_stream;
 mod json_depth_analyzer;
 
 use json_array_stream::JsonArrayStream;
-pub use json_array_stream::{stream_json_array, JsonStreamError};
+pub use json_array_encoder::{to_json_array_stream, to_json_array_stream_pretty};
+pub use json_array_stream::{
+    stream_json_array, stream_json_array_at, JsonArrayStreamBuilder, JsonStreamError,
+};
 
 pub struct ParsedStream<T, S, B>
 where
@@ -27,10 +31,23 @@ where
     where
         T: serde::de::Deserialize<'de>,
     {
-        return ParsedStream {
+        ParsedStream {
             stream: self,
             _t: PhantomData::<T>,
-        };
+        }
+    }
+
+    /// Deserializes each element as a dynamically-typed [`serde_json::Value`],
+    /// for arrays whose elements don't share a single concrete type.
+    pub fn values(self) -> ParsedStream<serde_json::Value, S, B> {
+        self.parsed::<serde_json::Value>()
+    }
+
+    /// Yields each element's byte buffer without deserializing it (whitespace
+    /// before an element is skipped, whitespace after it is kept), so callers can route elements to different deserializers (e.g. based
+    /// on a discriminator field) without paying for a full parse twice.
+    pub fn raw(self) -> JsonArrayStream<S, B> {
+        self
     }
 }
 
@@ -48,7 +65,7 @@ where
             Poll::Pending => Poll::Pending,
             Poll::Ready(opt) => Poll::Ready(opt.map(|res| {
                 res.and_then(|buffer| {
-                    serde_json::from_slice(&buffer).map_err(|err| JsonStreamError::from(err))
+                    serde_json::from_slice(&buffer).map_err(JsonStreamError::from)
                 })
             })),
         }
@@ -71,4 +88,33 @@ mod tests {
 
         assert_eq!(parsed.unwrap(), vec![-12., 11.1, 0.]);
     }
+
+    #[tokio::test]
+    async fn heterogeneous_values() {
+        let json = r#"[1,"two",{"three":3}]"#;
+        let stream = futures::stream::once(async { json.bytes() });
+        let values: Vec<_> = stream_json_array(stream)
+            .values()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            values,
+            vec![
+                serde_json::json!(1),
+                serde_json::json!("two"),
+                serde_json::json!({"three": 3}),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn raw_element_buffers() {
+        let json = "[1 ,2]";
+        let stream = futures::stream::once(async { json.bytes() });
+        let elements: Vec<_> = stream_json_array(stream).raw().try_collect().await.unwrap();
+
+        assert_eq!(elements, vec![b"1 ".to_vec(), b"2".to_vec()]);
+    }
 }